@@ -0,0 +1,130 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufReader, Read};
+
+use claxon::FlacReader;
+use hound::WavReader;
+use lewton::inside_ogg::OggStreamReader;
+
+/// Decoded audio, normalized to `[-1.0, 1.0]` and split into one buffer per
+/// channel. Mirrors `process_features::modules::audio_input` - kept as a
+/// separate copy since this binary is its own crate with no shared lib to
+/// put it in.
+pub struct AudioData {
+    pub channel_buffers: Vec<Vec<f32>>,
+    pub sample_rate: u32,
+    pub channels: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Backend {
+    Flac,
+    Wav,
+    Ogg,
+}
+
+/// Loads interleaved samples from `path` into per-channel buffers,
+/// auto-detecting the container from its extension, falling back to magic
+/// bytes when the extension is missing or unrecognized.
+///
+/// FLAC, WAV, and OGG Vorbis are implemented today; the `Backend` enum is the
+/// single place a WavPack/TTA/Monkey's Audio decoder would be added.
+pub fn load(path: &str) -> Result<AudioData, Box<dyn Error>> {
+    match detect_backend(path)? {
+        Backend::Flac => load_flac(path),
+        Backend::Wav => load_wav(path),
+        Backend::Ogg => load_ogg(path),
+    }
+}
+
+fn detect_backend(path: &str) -> Result<Backend, Box<dyn Error>> {
+    let ext = std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+
+    match ext.as_deref() {
+        Some("flac") => return Ok(Backend::Flac),
+        Some("wav") | Some("wave") => return Ok(Backend::Wav),
+        Some("ogg") | Some("oga") => return Ok(Backend::Ogg),
+        _ => {}
+    }
+
+    let mut magic = [0u8; 4];
+    let mut file = File::open(path)?;
+    file.read_exact(&mut magic)?;
+
+    match &magic {
+        b"fLaC" => Ok(Backend::Flac),
+        b"RIFF" => Ok(Backend::Wav),
+        b"OggS" => Ok(Backend::Ogg),
+        _ => Err(format!("Unrecognized audio container for '{}'", path).into()),
+    }
+}
+
+fn load_flac(path: &str) -> Result<AudioData, Box<dyn Error>> {
+    let mut reader = FlacReader::open(path)?;
+    let streaminfo = reader.streaminfo();
+    let sample_rate = streaminfo.sample_rate;
+    let channels = streaminfo.channels as usize;
+    let bits_per_sample = streaminfo.bits_per_sample;
+
+    if channels == 0 || sample_rate == 0 {
+        return Err("Invalid audio file: zero channels or sample rate".into());
+    }
+
+    let max_value = (1i32 << (bits_per_sample - 1)) as f32;
+    let mut channel_buffers: Vec<Vec<f32>> = vec![Vec::new(); channels];
+    for (i, sample) in reader.samples().enumerate() {
+        let s = sample? as f32 / max_value;
+        channel_buffers[i % channels].push(s);
+    }
+
+    Ok(AudioData { channel_buffers, sample_rate, channels })
+}
+
+fn load_ogg(path: &str) -> Result<AudioData, Box<dyn Error>> {
+    let file = File::open(path)?;
+    let mut ogg = OggStreamReader::new(BufReader::new(file))?;
+
+    let sample_rate = ogg.ident_hdr.audio_sample_rate;
+    let channels = ogg.ident_hdr.audio_channels as usize;
+
+    let mut channel_buffers: Vec<Vec<f32>> = vec![Vec::new(); channels];
+    while let Some(packet) = ogg.read_dec_packet_generic::<Vec<Vec<i16>>>()? {
+        for (chan, samples) in packet.into_iter().enumerate() {
+            for s in samples {
+                channel_buffers[chan].push(s as f32 / i16::MAX as f32);
+            }
+        }
+    }
+
+    Ok(AudioData { channel_buffers, sample_rate, channels })
+}
+
+fn load_wav(path: &str) -> Result<AudioData, Box<dyn Error>> {
+    let mut reader = WavReader::open(path)?;
+    let spec = reader.spec();
+    let sample_rate = spec.sample_rate;
+    let channels = spec.channels as usize;
+    let bits_per_sample = spec.bits_per_sample as u32;
+
+    let mut channel_buffers: Vec<Vec<f32>> = vec![Vec::new(); channels];
+
+    match spec.sample_format {
+        hound::SampleFormat::Int => {
+            let max_value = (1i32 << (bits_per_sample - 1)) as f32;
+            for (i, sample) in reader.samples::<i32>().enumerate() {
+                let s = sample? as f32 / max_value;
+                channel_buffers[i % channels].push(s);
+            }
+        }
+        hound::SampleFormat::Float => {
+            for (i, sample) in reader.samples::<f32>().enumerate() {
+                channel_buffers[i % channels].push(sample?);
+            }
+        }
+    }
+
+    Ok(AudioData { channel_buffers, sample_rate, channels })
+}
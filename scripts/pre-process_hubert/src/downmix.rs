@@ -0,0 +1,75 @@
+/// How to collapse a multichannel buffer down to fewer output channels.
+pub enum ChannelOp {
+    /// Channel count already matches; copy through unchanged.
+    Passthrough,
+    /// Output channel `d` is a straight copy of input channel `Reorder[d]`.
+    ///
+    /// No caller constructs this today (`itu_matrix` only ever returns
+    /// `Passthrough` or `Remix`) - kept as part of the `ChannelOp` surface
+    /// for a future custom `--downmix` layout that reorders without mixing.
+    #[allow(dead_code)]
+    Reorder(Vec<usize>),
+    /// Output channel `d` is `sum_s coeff[d * src_channels + s] * input[s]`.
+    Remix(Vec<f32>),
+}
+
+/// ITU-R BS.775 downmix coefficients: center and surrounds at `1/sqrt(2)`,
+/// LFE dropped, L/R mains passed through at unity gain.
+const ITU_CENTER: f32 = std::f32::consts::FRAC_1_SQRT_2;
+const ITU_SURROUND: f32 = std::f32::consts::FRAC_1_SQRT_2;
+
+/// Picks the conventional ITU downmix matrix for a decoded channel layout,
+/// falling back to naive averaging for layouts without a standard matrix.
+///
+/// Assumes the common 5.1 channel order: `[L, R, C, LFE, Ls, Rs]`.
+pub fn itu_matrix(src_channels: usize, dst_channels: usize) -> ChannelOp {
+    match (src_channels, dst_channels) {
+        (6, 2) => ChannelOp::Remix(vec![
+            // L                  R                   C             LFE   Ls             Rs
+            1.0, 0.0, ITU_CENTER, 0.0, ITU_SURROUND, 0.0,
+            0.0, 1.0, ITU_CENTER, 0.0, 0.0, ITU_SURROUND,
+        ]),
+        (6, 1) => ChannelOp::Remix(vec![
+            1.0, 1.0, ITU_CENTER, 0.0, ITU_SURROUND, ITU_SURROUND,
+        ]),
+        (2, 1) => ChannelOp::Remix(vec![0.5, 0.5]),
+        (n, m) if n == m => ChannelOp::Passthrough,
+        (n, m) => {
+            // No standard matrix for this layout: average every source
+            // channel equally into each destination channel.
+            let coeff = 1.0 / n as f32;
+            ChannelOp::Remix(vec![coeff; n * m])
+        }
+    }
+}
+
+/// Applies `op` to `channel_buffers` (one `Vec<f32>` per input channel),
+/// returning one `Vec<f32>` per output channel. Output samples are clamped
+/// to `[-1.0, 1.0]` after mixing.
+pub fn downmix(channel_buffers: &[Vec<f32>], op: &ChannelOp) -> Vec<Vec<f32>> {
+    let src_channels = channel_buffers.len();
+    let n_samples = channel_buffers.iter().map(|c| c.len()).max().unwrap_or(0);
+
+    match op {
+        ChannelOp::Passthrough => channel_buffers.to_vec(),
+        ChannelOp::Reorder(order) => order
+            .iter()
+            .map(|&src| channel_buffers[src].clone())
+            .collect(),
+        ChannelOp::Remix(coeff) => {
+            let dst_channels = coeff.len() / src_channels.max(1);
+            (0..dst_channels)
+                .map(|d| {
+                    (0..n_samples)
+                        .map(|i| {
+                            let mixed: f32 = (0..src_channels)
+                                .map(|s| coeff[d * src_channels + s] * channel_buffers[s].get(i).copied().unwrap_or(0.0))
+                                .sum();
+                            mixed.clamp(-1.0, 1.0)
+                        })
+                        .collect()
+                })
+                .collect()
+        }
+    }
+}
@@ -1,67 +1,124 @@
 use std::env;
-use claxon::FlacReader;
 use rubato::{FftFixedIn, Resampler};
 use ndarray::Array1;
 use ndarray_npy::write_npy;
 
+mod audio_input;
+mod downmix;
+mod sinc_resampler;
+use downmix::ChannelOp;
+use sinc_resampler::SincResampler;
+
+/// Which resampler implementation to use when the source and target sample
+/// rates differ.
+#[derive(Clone, Copy, PartialEq)]
+enum ResamplerKind {
+    /// `rubato::FftFixedIn` - fast, but can introduce artifacts at
+    /// non-integer rate ratios.
+    Fft,
+    /// Polyphase Kaiser-windowed sinc - slower, cleaner transition band.
+    Sinc,
+}
+
+impl ResamplerKind {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "fft" => Some(ResamplerKind::Fft),
+            "sinc" => Some(ResamplerKind::Sinc),
+            _ => None,
+        }
+    }
+}
+
+/// How multichannel input is collapsed down before resampling/saving.
+#[derive(Clone, Copy, PartialEq)]
+enum DownmixMode {
+    /// Naive equal-weight average straight to mono (legacy behavior).
+    Mono,
+    /// ITU-weighted downmix to stereo, then equal-weight average to mono.
+    Stereo,
+    /// ITU-weighted downmix straight to mono (center/surrounds at 1/sqrt(2), LFE dropped).
+    Itu,
+}
+
+impl DownmixMode {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "mono" => Some(DownmixMode::Mono),
+            "stereo" => Some(DownmixMode::Stereo),
+            "itu" => Some(DownmixMode::Itu),
+            _ => None,
+        }
+    }
+}
+
 /// Preprocess FLAC audio for HuBERT:
 /// - Converts to mono
 /// - Resamples to target_sr
 /// - Normalizes amplitude
 /// - Saves as 1D .npy
-fn preprocess_flac(input_path: &str, output_path: &str, target_sr: u32) -> Result<(), Box<dyn std::error::Error>> {
-    // --- Open FLAC ---
-    let mut reader = FlacReader::open(input_path)?;
-    let streaminfo = reader.streaminfo();
-    let sample_rate = streaminfo.sample_rate;
-    let channels = streaminfo.channels as usize;
-
-    // --- Read samples ---
-    let mut samples: Vec<f32> = Vec::new();
-    let bits_per_sample = streaminfo.bits_per_sample;
-    let max_value = (1i32 << (bits_per_sample - 1)) as f32;
-    if channels == 0 || sample_rate == 0 {
-        return Err("Invalid audio file: zero channels or sample rate".into());
-    }
-    for sample in reader.samples() {
-        let s = sample? as f32 / max_value;
-        samples.push(s);
-    }
+fn preprocess_flac(
+    input_path: &str,
+    output_path: &str,
+    target_sr: u32,
+    resampler_kind: ResamplerKind,
+    sinc_order: usize,
+    downmix_mode: DownmixMode,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // --- Load audio ---
+    let audio = audio_input::load(input_path)?;
+    let sample_rate = audio.sample_rate;
+    let channels = audio.channels;
 
-    // --- Split into channels ---
-    let mut channel_buffers: Vec<Vec<f32>> = vec![Vec::new(); channels];
-    for (i, s) in samples.iter().enumerate() {
-        channel_buffers[i % channels].push(*s);
-    }
+    // --- Downmix to the working channel layout before resampling ---
+    let dst_channels = if downmix_mode == DownmixMode::Stereo { 2 } else { 1 };
+    let op = match downmix_mode {
+        DownmixMode::Mono => {
+            let coeff = 1.0 / channels.max(1) as f32;
+            ChannelOp::Remix(vec![coeff; channels])
+        }
+        DownmixMode::Stereo | DownmixMode::Itu => downmix::itu_matrix(channels, dst_channels),
+    };
+    let channel_buffers = downmix::downmix(&audio.channel_buffers, &op);
+    let channels = channel_buffers.len();
 
     // --- Resample if needed ---
-    let mono_samples: Vec<f32> = if sample_rate != target_sr {
-        let chunk_size = 1024;
-        let chunk_size = (sample_rate as usize).min(4096); // Adaptive chunk size
-        let mut resampler = FftFixedIn::<f32>::new(
-            sample_rate as usize,
-            target_sr as usize,
-            chunk_size,
-            1, // Single thread to avoid potential issues
-            channels,
-        )?;
-
-        
-
-        let resampled_channels: Vec<Vec<f32>> = resampler.process(&channel_buffers, None)?;
-        let n_samples = resampled_channels[0].len();
-        // Convert to mono
-        (0..n_samples)
-            .map(|i| resampled_channels.iter().map(|c| c[i]).sum::<f32>() / channels as f32)
-            .collect()
+    let resampled_channels: Vec<Vec<f32>> = if sample_rate != target_sr {
+        match resampler_kind {
+            ResamplerKind::Fft => {
+                let chunk_size = 1024;
+                let chunk_size = (sample_rate as usize).min(4096); // Adaptive chunk size
+                let mut resampler = FftFixedIn::<f32>::new(
+                    sample_rate as usize,
+                    target_sr as usize,
+                    chunk_size,
+                    1, // Single thread to avoid potential issues
+                    channels,
+                )?;
+
+                resampler.process(&channel_buffers, None)?
+            }
+            ResamplerKind::Sinc => {
+                let sinc = SincResampler::new(sample_rate as usize, target_sr as usize, sinc_order);
+                channel_buffers
+                    .iter()
+                    .map(|chan| {
+                        let chan_f64: Vec<f64> = chan.iter().map(|&s| s as f64).collect();
+                        sinc.process(&chan_f64).into_iter().map(|s| s as f32).collect()
+                    })
+                    .collect()
+            }
+        }
     } else {
-        // No resampling, just convert to mono
-        let n_samples = channel_buffers[0].len();
-        (0..n_samples)
-            .map(|i| channel_buffers.iter().map(|c| c[i]).sum::<f32>() / channels as f32)
-            .collect()
+        channel_buffers
     };
 
+    // --- Collapse to mono ---
+    let n_samples = resampled_channels[0].len();
+    let mono_samples: Vec<f32> = (0..n_samples)
+        .map(|i| resampled_channels.iter().map(|c| c[i]).sum::<f32>() / channels as f32)
+        .collect();
+
     if mono_samples.is_empty() {
         return Err("No audio data found".into());
     }
@@ -89,8 +146,11 @@ fn preprocess_flac(input_path: &str, output_path: &str, target_sr: u32) -> Resul
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = env::args().collect();
-    if args.len() != 4 {
-        eprintln!("Usage: {} <input_audio.flac> <output.npy> <target_sample_rate>", args[0]);
+    if args.len() < 4 {
+        eprintln!(
+            "Usage: {} <input_audio.flac> <output.npy> <target_sample_rate> [fft|sinc] [sinc_order] [--downmix mono|stereo|itu]",
+            args[0]
+        );
         std::process::exit(1);
     }
 
@@ -98,7 +158,41 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let output_path = &args[2];
     let target_sr: u32 = args[3].parse()?;
 
-    preprocess_flac(input_path, output_path, target_sr)?;
+    // Pull the `--downmix <mode>` flag out of the remaining args; whatever
+    // positional arguments are left over are [resampler] [sinc_order].
+    let mut downmix_mode = DownmixMode::Itu;
+    let mut positional: Vec<&String> = Vec::new();
+    let mut i = 4;
+    while i < args.len() {
+        if args[i] == "--downmix" {
+            let value = args.get(i + 1).unwrap_or_else(|| {
+                eprintln!("Error: --downmix requires mono, stereo, or itu");
+                std::process::exit(1);
+            });
+            downmix_mode = DownmixMode::parse(value).unwrap_or_else(|| {
+                eprintln!("Error: unknown downmix mode '{}', expected 'mono', 'stereo', or 'itu'", value);
+                std::process::exit(1);
+            });
+            i += 2;
+        } else {
+            positional.push(&args[i]);
+            i += 1;
+        }
+    }
+
+    let resampler_kind = match positional.get(0) {
+        Some(kind) => ResamplerKind::parse(kind).unwrap_or_else(|| {
+            eprintln!("Error: unknown resampler '{}', expected 'fft' or 'sinc'", kind);
+            std::process::exit(1);
+        }),
+        None => ResamplerKind::Fft,
+    };
+    let sinc_order: usize = match positional.get(1) {
+        Some(order) => order.parse()?,
+        None => 16,
+    };
+
+    preprocess_flac(input_path, output_path, target_sr, resampler_kind, sinc_order, downmix_mode)?;
 
     Ok(())
 }
@@ -0,0 +1,19 @@
+use std::error::Error;
+
+/// A single pluggable audio analyzer. Implementors wrap one of the
+/// `modules::*` processors and give it a stable CLI name; the dispatcher in
+/// `main.rs` turns that name into a `--<name>-in`/`--<name>-out` flag pair
+/// and generates `print_usage` output automatically.
+///
+/// Adding a new analyzer is a one-site change: implement this trait and push
+/// an instance into the registry built in `main`.
+pub trait AudioFeature {
+    /// CLI flag stem, e.g. `"rms"` for `--rms-in`/`--rms-out`.
+    fn name(&self) -> &str;
+
+    /// One-line description shown in `print_usage`.
+    fn description(&self) -> &str;
+
+    /// Runs the analyzer, reading `input` and writing the CSV to `output`.
+    fn process(&self, input: &str, output: &str) -> Result<(), Box<dyn Error>>;
+}
@@ -1,210 +1,342 @@
 use std::env;
 
+mod feature;
 mod modules {
+    pub mod audio_input;
+    pub mod resample;
     pub mod rms_energy;
     pub mod pitch;
     pub mod spectral_features;
     pub mod jitter_shimmer;
     pub mod formant_analysis;
+    pub mod chroma;
+    pub mod combined;
+    pub mod mfcc;
 }
 
-use modules::{rms_energy, pitch, spectral_features, jitter_shimmer, formant_analysis};
+use feature::AudioFeature;
+use modules::combined::FeatureSet;
+use modules::spectral_features::WindowType;
+use modules::{rms_energy, pitch, spectral_features, jitter_shimmer, formant_analysis, chroma, combined, mfcc};
 
-fn print_usage() {
+macro_rules! simple_feature {
+    ($struct_name:ident, $name:expr, $description:expr, $module:ident) => {
+        struct $struct_name;
+
+        impl AudioFeature for $struct_name {
+            fn name(&self) -> &str {
+                $name
+            }
+
+            fn description(&self) -> &str {
+                $description
+            }
+
+            fn process(&self, input: &str, output: &str) -> Result<(), Box<dyn std::error::Error>> {
+                $module::process(input, output)
+            }
+        }
+    };
+}
+
+simple_feature!(RmsFeature, "rms", "RMS energy and total energy analysis", rms_energy);
+simple_feature!(PitchFeature, "pitch", "Pitch detection and analysis", pitch);
+
+/// Spectral features, with a configurable hop size and analysis window
+/// (set via `--spectral-hop`/`--spectral-window`) - unlike the other
+/// features this one carries state, so it can't go through `simple_feature!`.
+struct SpectralFeature {
+    hop_ms: Option<f64>,
+    window: WindowType,
+}
+
+impl AudioFeature for SpectralFeature {
+    fn name(&self) -> &str {
+        "spectral"
+    }
+
+    fn description(&self) -> &str {
+        "Spectral features (centroid, rolloff, bandwidth, flatness, flux, zero-crossing rate)"
+    }
+
+    fn process(&self, input: &str, output: &str) -> Result<(), Box<dyn std::error::Error>> {
+        spectral_features::process_with_config(input, output, self.hop_ms, self.window)
+    }
+}
+
+simple_feature!(
+    JitterFeature,
+    "jitter",
+    "Jitter, shimmer, and harmonics-to-noise ratio analysis",
+    jitter_shimmer
+);
+
+/// Formant analysis, with an optional `--formant-resample` target rate so
+/// frame length, hop length, and LPC order are comparable across input
+/// files recorded at different sample rates.
+struct FormantFeature {
+    target_sr: Option<usize>,
+}
+
+impl AudioFeature for FormantFeature {
+    fn name(&self) -> &str {
+        "formant"
+    }
+
+    fn description(&self) -> &str {
+        "Formant frequency analysis (F1, F2, F3, F4)"
+    }
+
+    fn process(&self, input: &str, output: &str) -> Result<(), Box<dyn std::error::Error>> {
+        formant_analysis::process_with_config(input, output, self.target_sr)
+    }
+}
+
+simple_feature!(
+    ChromaFeature,
+    "chroma",
+    "12-bin chromagram per frame plus overall key/mode estimate",
+    chroma
+);
+simple_feature!(
+    MfccFeature,
+    "mfcc",
+    "Mel-frequency cepstral coefficients per frame",
+    mfcc
+);
+
+/// Runs a `FeatureSet` (selected via `--rms`/`--energy`/`--formants`/
+/// `--spectral`/`--all`) through a single shared decode-and-framing pass,
+/// merging every enabled extractor's columns into one wide CSV - unlike the
+/// features above, which each decode and frame the audio independently.
+struct CombinedFeature {
+    features: FeatureSet,
+}
+
+impl AudioFeature for CombinedFeature {
+    fn name(&self) -> &str {
+        "combined"
+    }
+
+    fn description(&self) -> &str {
+        "Combined multi-feature extractor (--rms/--energy/--formants/--spectral/--all) sharing one decode/framing pass"
+    }
+
+    fn process(&self, input: &str, output: &str) -> Result<(), Box<dyn std::error::Error>> {
+        combined::process(input, output, self.features)
+    }
+}
+
+fn registry(
+    spectral_hop_ms: Option<f64>,
+    spectral_window: WindowType,
+    formant_target_sr: Option<usize>,
+    combined_features: FeatureSet,
+) -> Vec<Box<dyn AudioFeature>> {
+    vec![
+        Box::new(RmsFeature),
+        Box::new(PitchFeature),
+        Box::new(SpectralFeature { hop_ms: spectral_hop_ms, window: spectral_window }),
+        Box::new(JitterFeature),
+        Box::new(FormantFeature { target_sr: formant_target_sr }),
+        Box::new(ChromaFeature),
+        Box::new(CombinedFeature { features: combined_features }),
+        Box::new(MfccFeature),
+    ]
+}
+
+fn print_usage(registry: &[Box<dyn AudioFeature>]) {
     let prog_name = env::args().nth(0).unwrap_or_default();
     eprintln!("Usage:");
-    eprintln!("  {} --rms-in <input.flac> --rms-out <output.csv>", prog_name);
-    eprintln!("  {} --pitch-in <input.flac> --pitch-out <output.csv>", prog_name);
-    eprintln!("  {} --spectral-in <input.flac> --spectral-out <output.csv>", prog_name);
-    eprintln!("  {} --jitter-in <input.flac> --jitter-out <output.csv>", prog_name);
-    eprintln!("  {} --formant-in <input.flac> --formant-out <output.csv>", prog_name);
-    eprintln!("  {} --rms-in <rms_input.flac> --rms-out <rms_output.csv> --pitch-in <pitch_input.flac> --pitch-out <pitch_output.csv>", prog_name);
-    eprintln!("  {} --spectral-in <input.flac> --spectral-out <output.csv> --jitter-in <input.flac> --jitter-out <output.csv>", prog_name);
-    eprintln!("  {} --formant-in <input.flac> --formant-out <output.csv> --pitch-in <input.flac> --pitch-out <output.csv>", prog_name);
+    for feature in registry {
+        eprintln!(
+            "  {} --{}-in <input.flac> --{}-out <output.csv>",
+            prog_name, feature.name(), feature.name()
+        );
+    }
+    eprintln!(
+        "  {} --rms-in <input.flac> --rms-out <rms_output.csv> --pitch-in <input.flac> --pitch-out <pitch_output.csv>",
+        prog_name
+    );
     eprintln!("");
     eprintln!("Features:");
-    eprintln!("  --rms-*       : RMS energy and total energy analysis");
-    eprintln!("  --pitch-*     : Pitch detection and analysis");
-    eprintln!("  --spectral-*  : Spectral features (centroid, rolloff, bandwidth, flatness, flux, zero-crossing rate)");
-    eprintln!("  --jitter-*    : Jitter, shimmer, and harmonics-to-noise ratio analysis");
-    eprintln!("  --formant-*   : Formant frequency analysis (F1, F2, F3, F4)");
+    for feature in registry {
+        eprintln!("  --{}-*{} : {}", feature.name(), " ".repeat(8usize.saturating_sub(feature.name().len())), feature.description());
+    }
+    eprintln!("");
+    eprintln!("Spectral options:");
+    eprintln!("  --spectral-hop <ms>      : frame hop in milliseconds (default: no overlap, hop = frame length)");
+    eprintln!("  --spectral-window <name> : hamming (default), hann, blackman, or rectangular");
+    eprintln!("");
+    eprintln!("Formant options:");
+    eprintln!("  --formant-resample <hz>  : resample to this rate before formant analysis (default: none, use source rate)");
+    eprintln!("");
+    eprintln!("Combined options (used with --combined-in/--combined-out):");
+    eprintln!("  --rms --energy --formants --spectral : enable one or more extractors for the shared pass");
+    eprintln!("  --all                                 : enable every extractor");
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let args: Vec<String> = env::args().collect();
-    
+
     if args.len() < 3 {
-        print_usage();
+        print_usage(&registry(None, WindowType::default(), None, FeatureSet::default()));
         std::process::exit(1);
     }
 
-    let mut rms_input: Option<String> = None;
-    let mut rms_output: Option<String> = None;
-    let mut pitch_input: Option<String> = None;
-    let mut pitch_output: Option<String> = None;
-    let mut spectral_input: Option<String> = None;
-    let mut spectral_output: Option<String> = None;
-    let mut jitter_input: Option<String> = None;
-    let mut jitter_output: Option<String> = None;
-    let mut formant_input: Option<String> = None;
-    let mut formant_output: Option<String> = None;
-
-    // Parse arguments
+    // Pull the `--spectral-hop`/`--spectral-window`/`--formant-resample`/
+    // `--rms`/`--energy`/`--formants`/`--spectral`/`--all` options out of the
+    // argument list; everything left over goes through the generic
+    // `--{name}-in`/`--{name}-out` loop below.
+    let mut spectral_hop_ms: Option<f64> = None;
+    let mut spectral_window = WindowType::default();
+    let mut formant_target_sr: Option<usize> = None;
+    let mut combined_features = FeatureSet::default();
+    let mut remaining_args: Vec<String> = vec![args[0].clone()];
     let mut i = 1;
     while i < args.len() {
         match args[i].as_str() {
-            "--rms-in" => {
-                if i + 1 >= args.len() {
-                    eprintln!("Error: --rms-in requires a file path");
+            "--spectral-hop" => {
+                let value = args.get(i + 1).unwrap_or_else(|| {
+                    eprintln!("Error: --spectral-hop requires a value in milliseconds");
                     std::process::exit(1);
-                }
-                rms_input = Some(args[i + 1].clone());
-                i += 2;
-            }
-            "--rms-out" => {
-                if i + 1 >= args.len() {
-                    eprintln!("Error: --rms-out requires a file path");
+                });
+                spectral_hop_ms = Some(value.parse().unwrap_or_else(|_| {
+                    eprintln!("Error: --spectral-hop value '{}' is not a number", value);
                     std::process::exit(1);
-                }
-                rms_output = Some(args[i + 1].clone());
+                }));
                 i += 2;
             }
-            "--pitch-in" => {
-                if i + 1 >= args.len() {
-                    eprintln!("Error: --pitch-in requires a file path");
+            "--spectral-window" => {
+                let value = args.get(i + 1).unwrap_or_else(|| {
+                    eprintln!("Error: --spectral-window requires a value");
                     std::process::exit(1);
-                }
-                pitch_input = Some(args[i + 1].clone());
-                i += 2;
-            }
-            "--pitch-out" => {
-                if i + 1 >= args.len() {
-                    eprintln!("Error: --pitch-out requires a file path");
+                });
+                spectral_window = WindowType::parse(value).unwrap_or_else(|| {
+                    eprintln!(
+                        "Error: unknown window '{}', expected 'hamming', 'hann', 'blackman', or 'rectangular'",
+                        value
+                    );
                     std::process::exit(1);
-                }
-                pitch_output = Some(args[i + 1].clone());
+                });
                 i += 2;
             }
-            "--spectral-in" => {
-                if i + 1 >= args.len() {
-                    eprintln!("Error: --spectral-in requires a file path");
+            "--formant-resample" => {
+                let value = args.get(i + 1).unwrap_or_else(|| {
+                    eprintln!("Error: --formant-resample requires a value in Hz");
                     std::process::exit(1);
-                }
-                spectral_input = Some(args[i + 1].clone());
-                i += 2;
-            }
-            "--spectral-out" => {
-                if i + 1 >= args.len() {
-                    eprintln!("Error: --spectral-out requires a file path");
+                });
+                formant_target_sr = Some(value.parse().unwrap_or_else(|_| {
+                    eprintln!("Error: --formant-resample value '{}' is not a number", value);
                     std::process::exit(1);
-                }
-                spectral_output = Some(args[i + 1].clone());
+                }));
                 i += 2;
             }
-            "--jitter-in" => {
-                if i + 1 >= args.len() {
-                    eprintln!("Error: --jitter-in requires a file path");
-                    std::process::exit(1);
-                }
-                jitter_input = Some(args[i + 1].clone());
-                i += 2;
+            "--rms" => {
+                combined_features.rms = true;
+                i += 1;
             }
-            "--jitter-out" => {
-                if i + 1 >= args.len() {
-                    eprintln!("Error: --jitter-out requires a file path");
-                    std::process::exit(1);
-                }
-                jitter_output = Some(args[i + 1].clone());
-                i += 2;
+            "--energy" => {
+                combined_features.energy = true;
+                i += 1;
             }
-            "--formant-in" => {
-                if i + 1 >= args.len() {
-                    eprintln!("Error: --formant-in requires a file path");
-                    std::process::exit(1);
-                }
-                formant_input = Some(args[i + 1].clone());
-                i += 2;
+            "--formants" => {
+                combined_features.formants = true;
+                i += 1;
             }
-            "--formant-out" => {
-                if i + 1 >= args.len() {
-                    eprintln!("Error: --formant-out requires a file path");
-                    std::process::exit(1);
-                }
-                formant_output = Some(args[i + 1].clone());
-                i += 2;
+            "--spectral" => {
+                combined_features.spectral = true;
+                i += 1;
+            }
+            "--all" => {
+                combined_features = FeatureSet { rms: true, energy: true, formants: true, spectral: true };
+                i += 1;
             }
             _ => {
-                eprintln!("Unknown argument: {}", args[i]);
-                print_usage();
-                std::process::exit(1);
+                remaining_args.push(args[i].clone());
+                i += 1;
             }
         }
     }
 
-    // Validate argument combinations
-    let run_rms = rms_input.is_some() || rms_output.is_some();
-    let run_pitch = pitch_input.is_some() || pitch_output.is_some();
-    let run_spectral = spectral_input.is_some() || spectral_output.is_some();
-    let run_jitter = jitter_input.is_some() || jitter_output.is_some();
-    let run_formant = formant_input.is_some() || formant_output.is_some();
-
-    if run_rms && (rms_input.is_none() || rms_output.is_none()) {
-        eprintln!("Error: Both --rms-in and --rms-out are required for RMS processing");
-        std::process::exit(1);
-    }
+    let registry = registry(spectral_hop_ms, spectral_window, formant_target_sr, combined_features);
+    let args = remaining_args;
 
-    if run_pitch && (pitch_input.is_none() || pitch_output.is_none()) {
-        eprintln!("Error: Both --pitch-in and --pitch-out are required for pitch processing");
-        std::process::exit(1);
-    }
+    // name -> (input, output)
+    let mut selected: Vec<(usize, Option<String>, Option<String>)> =
+        registry.iter().enumerate().map(|(i, _)| (i, None, None)).collect();
 
-    if run_spectral && (spectral_input.is_none() || spectral_output.is_none()) {
-        eprintln!("Error: Both --spectral-in and --spectral-out are required for spectral processing");
-        std::process::exit(1);
-    }
+    let mut i = 1;
+    while i < args.len() {
+        let arg = args[i].as_str();
 
-    if run_jitter && (jitter_input.is_none() || jitter_output.is_none()) {
-        eprintln!("Error: Both --jitter-in and --jitter-out are required for jitter/shimmer processing");
-        std::process::exit(1);
+        let matched = registry.iter().enumerate().find_map(|(idx, feature)| {
+            let in_flag = format!("--{}-in", feature.name());
+            let out_flag = format!("--{}-out", feature.name());
+            if arg == in_flag {
+                Some((idx, true))
+            } else if arg == out_flag {
+                Some((idx, false))
+            } else {
+                None
+            }
+        });
+
+        match matched {
+            Some((idx, is_input)) => {
+                if i + 1 >= args.len() {
+                    eprintln!("Error: {} requires a file path", arg);
+                    std::process::exit(1);
+                }
+                if is_input {
+                    selected[idx].1 = Some(args[i + 1].clone());
+                } else {
+                    selected[idx].2 = Some(args[i + 1].clone());
+                }
+                i += 2;
+            }
+            None => {
+                eprintln!("Unknown argument: {}", arg);
+                print_usage(&registry);
+                std::process::exit(1);
+            }
+        }
     }
 
-    if run_formant && (formant_input.is_none() || formant_output.is_none()) {
-        eprintln!("Error: Both --formant-in and --formant-out are required for formant processing");
-        std::process::exit(1);
+    // Validate argument combinations
+    let mut any_selected = false;
+    for (idx, input, output) in &selected {
+        let feature = &registry[*idx];
+        let requested = input.is_some() || output.is_some();
+        if requested && (input.is_none() || output.is_none()) {
+            eprintln!(
+                "Error: Both --{}-in and --{}-out are required for {} processing",
+                feature.name(), feature.name(), feature.name()
+            );
+            std::process::exit(1);
+        }
+        if requested && feature.name() == "combined" && !combined_features.any() {
+            eprintln!("Error: --combined-in/--combined-out requires at least one of --rms, --energy, --formants, --spectral, or --all");
+            std::process::exit(1);
+        }
+        any_selected |= requested;
     }
 
-    if !run_rms && !run_pitch && !run_spectral && !run_jitter && !run_formant {
+    if !any_selected {
         eprintln!("Error: No processing specified");
-        print_usage();
+        print_usage(&registry);
         std::process::exit(1);
     }
 
     // Run processing
-    if run_rms {
-        println!("=== Running RMS Energy Analysis ===");
-        rms_energy::process(&rms_input.unwrap(), &rms_output.unwrap())?;
-    }
-
-    if run_pitch {
-        println!("=== Running Pitch Analysis ===");
-        pitch::process(&pitch_input.unwrap(), &pitch_output.unwrap())?;
-    }
-
-    if run_spectral {
-        println!("=== Running Spectral Features Analysis ===");
-        spectral_features::process(&spectral_input.unwrap(), &spectral_output.unwrap())?;
-    }
-
-    if run_jitter {
-        println!("=== Running Jitter/Shimmer Analysis ===");
-        jitter_shimmer::process(&jitter_input.unwrap(), &jitter_output.unwrap())?;
-    }
-
-    if run_formant {
-        println!("=== Running Formant Analysis ===");
-        formant_analysis::process(&formant_input.unwrap(), &formant_output.unwrap())?;
+    for (idx, input, output) in selected {
+        if let (Some(input), Some(output)) = (input, output) {
+            let feature = &registry[idx];
+            println!("=== Running {} ===", feature.description());
+            feature.process(&input, &output)?;
+        }
     }
 
     println!("=== All processing complete ===");
     Ok(())
-}
\ No newline at end of file
+}
@@ -0,0 +1,134 @@
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufReader, Read};
+
+use claxon::FlacReader;
+use hound::WavReader;
+use lewton::inside_ogg::OggStreamReader;
+
+/// Decoded audio, normalized to `[-1.0, 1.0]` and split into one buffer per
+/// channel. Every backend in this module produces one of these regardless of
+/// the source container, so the analysis modules never touch a
+/// format-specific reader directly.
+pub struct AudioData {
+    pub channel_buffers: Vec<Vec<f64>>,
+    pub sample_rate: usize,
+    pub channels: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Backend {
+    Flac,
+    Wav,
+    Ogg,
+}
+
+/// Loads interleaved samples from `path` into per-channel buffers,
+/// auto-detecting the container from its extension, falling back to magic
+/// bytes when the extension is missing or unrecognized.
+///
+/// FLAC, WAV, and OGG Vorbis are implemented today; the `Backend` enum is the
+/// single place a WavPack/TTA/Monkey's Audio decoder would be added.
+pub fn load(path: &str) -> Result<AudioData, Box<dyn Error>> {
+    match detect_backend(path)? {
+        Backend::Flac => load_flac(path),
+        Backend::Wav => load_wav(path),
+        Backend::Ogg => load_ogg(path),
+    }
+}
+
+fn detect_backend(path: &str) -> Result<Backend, Box<dyn Error>> {
+    let ext = std::path::Path::new(path)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+
+    match ext.as_deref() {
+        Some("flac") => return Ok(Backend::Flac),
+        Some("wav") | Some("wave") => return Ok(Backend::Wav),
+        Some("ogg") | Some("oga") => return Ok(Backend::Ogg),
+        _ => {}
+    }
+
+    // Extension missing/unrecognized: sniff the magic bytes.
+    let mut magic = [0u8; 4];
+    let mut file = File::open(path)?;
+    file.read_exact(&mut magic)?;
+
+    match &magic {
+        b"fLaC" => Ok(Backend::Flac),
+        b"RIFF" => Ok(Backend::Wav),
+        b"OggS" => Ok(Backend::Ogg),
+        _ => Err(format!("Unrecognized audio container for '{}'", path).into()),
+    }
+}
+
+fn load_flac(path: &str) -> Result<AudioData, Box<dyn Error>> {
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+    let mut flac = FlacReader::new(reader)?;
+
+    let sample_rate = flac.streaminfo().sample_rate as usize;
+    let channels = flac.streaminfo().channels as usize;
+    let bits_per_sample = flac.streaminfo().bits_per_sample;
+    let total_samples = flac.streaminfo().samples.unwrap_or(0) as usize;
+
+    let mut channel_buffers: Vec<Vec<f64>> =
+        vec![Vec::with_capacity(total_samples / channels.max(1)); channels];
+
+    let full_scale = (1i64 << (bits_per_sample - 1)) as f64;
+    for (i, sample) in flac.samples().enumerate() {
+        let s = sample?;
+        let chan = i % channels;
+        channel_buffers[chan].push(s as f64 / full_scale);
+    }
+
+    Ok(AudioData { channel_buffers, sample_rate, channels })
+}
+
+fn load_ogg(path: &str) -> Result<AudioData, Box<dyn Error>> {
+    let file = File::open(path)?;
+    let mut ogg = OggStreamReader::new(BufReader::new(file))?;
+
+    let sample_rate = ogg.ident_hdr.audio_sample_rate as usize;
+    let channels = ogg.ident_hdr.audio_channels as usize;
+
+    let mut channel_buffers: Vec<Vec<f64>> = vec![Vec::new(); channels];
+    while let Some(packet) = ogg.read_dec_packet_generic::<Vec<Vec<i16>>>()? {
+        for (chan, samples) in packet.into_iter().enumerate() {
+            for s in samples {
+                channel_buffers[chan].push(s as f64 / i16::MAX as f64);
+            }
+        }
+    }
+
+    Ok(AudioData { channel_buffers, sample_rate, channels })
+}
+
+fn load_wav(path: &str) -> Result<AudioData, Box<dyn Error>> {
+    let mut reader = WavReader::open(path)?;
+    let spec = reader.spec();
+    let sample_rate = spec.sample_rate as usize;
+    let channels = spec.channels as usize;
+    let bits_per_sample = spec.bits_per_sample as u32;
+
+    let mut channel_buffers: Vec<Vec<f64>> = vec![Vec::new(); channels];
+
+    match spec.sample_format {
+        hound::SampleFormat::Int => {
+            let full_scale = (1i64 << (bits_per_sample - 1)) as f64;
+            for (i, sample) in reader.samples::<i32>().enumerate() {
+                let s = sample?;
+                channel_buffers[i % channels].push(s as f64 / full_scale);
+            }
+        }
+        hound::SampleFormat::Float => {
+            for (i, sample) in reader.samples::<f32>().enumerate() {
+                let s = sample?;
+                channel_buffers[i % channels].push(s as f64);
+            }
+        }
+    }
+
+    Ok(AudioData { channel_buffers, sample_rate, channels })
+}
@@ -0,0 +1,207 @@
+use csv::Writer;
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use rustfft::{FftPlanner, num_complex::Complex};
+
+use super::audio_input;
+use super::spectral_features::{self, WindowType};
+
+const PITCH_CLASSES: [&str; 12] = [
+    "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+];
+
+const MAJOR_PROFILE: [f64; 12] = [
+    6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88,
+];
+const MINOR_PROFILE: [f64; 12] = [
+    6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17,
+];
+
+pub fn process(input_path: &str, output_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Input audio file: {}", input_path);
+    println!("Output CSV file: {}", output_path);
+
+    // --- LOAD AUDIO ---
+    let audio = audio_input::load(input_path)?;
+    let samplerate = audio.sample_rate;
+    let channels = audio.channels;
+    let channel_buffers = audio.channel_buffers;
+
+    println!("Sample rate: {} Hz, {} channel(s)", samplerate, channels);
+
+    // --- FRAME PARAMETERS (matches spectral_features::process) ---
+    let frame_len = (0.025 * samplerate as f64) as usize; // 25ms frames
+    let fft_size = frame_len.next_power_of_two(); // FFT size (power of 2)
+    println!("Calculating chromagram using {}-sample frames (~25ms), FFT size: {}", frame_len, fft_size);
+
+    // --- MULTIPROGRESS ---
+    let m = MultiProgress::new();
+    let status_bar = m.add(ProgressBar::new(1));
+    status_bar.set_style(ProgressStyle::default_bar().template("{msg}").unwrap());
+
+    // --- FFT SETUP ---
+    let mut planner = FftPlanner::new();
+    let fft = planner.plan_fft_forward(fft_size);
+
+    // --- CSV SETUP ---
+    let mut writer = Writer::from_path(output_path)?;
+    let mut headers = vec!["time_sec".to_string()];
+    for c in 0..channels {
+        for pc in PITCH_CLASSES.iter() {
+            headers.push(format!("chan{}_chroma_{}", c + 1, pc));
+        }
+    }
+    writer.write_record(&headers)?;
+
+    // --- CALCULATE CHROMAGRAM PER CHANNEL ---
+    status_bar.set_message("[ == CALCULATING CHROMAGRAM == ]");
+    let max_len = channel_buffers.iter().map(|v| v.len()).max().unwrap_or(0);
+    let frame_hop = frame_len; // non-overlapping frames
+
+    let mut chroma_sums: Vec<[f64; 12]> = vec![[0.0; 12]; channels];
+    let mut chroma_frame_counts: Vec<usize> = vec![0; channels];
+
+    for start in (0..max_len).step_by(frame_hop) {
+        let time_sec = start as f64 / samplerate as f64;
+        let mut row: Vec<String> = vec![format!("{:.4}", time_sec)];
+
+        for (chan_idx, chan) in channel_buffers.iter().enumerate() {
+            if start >= chan.len() {
+                for _ in 0..12 {
+                    row.push("".to_string());
+                }
+                continue;
+            }
+
+            let end = (start + frame_len).min(chan.len());
+            let frame = &chan[start..end];
+
+            // Prepare FFT input (pad with zeros if necessary)
+            let mut fft_input: Vec<Complex<f64>> = frame.iter()
+                .map(|&x| Complex::new(x, 0.0))
+                .collect();
+            let real_len = fft_input.len();
+            fft_input.resize(fft_size, Complex::new(0.0, 0.0));
+
+            // Apply window only over the real (unpadded) frame length, so
+            // zero-padding at the FFT tail isn't tapered as if it were signal.
+            spectral_features::apply_window(&mut fft_input[..real_len], WindowType::default());
+            fft.process(&mut fft_input);
+
+            let magnitude_spectrum: Vec<f64> = fft_input[0..fft_size / 2]
+                .iter()
+                .map(|c| c.norm())
+                .collect();
+
+            let chroma = calculate_chroma(&magnitude_spectrum, samplerate, fft_size);
+
+            for (c, &v) in chroma_sums[chan_idx].iter_mut().zip(chroma.iter()) {
+                *c += v;
+            }
+            chroma_frame_counts[chan_idx] += 1;
+
+            for v in chroma.iter() {
+                row.push(format!("{:.6}", v));
+            }
+        }
+
+        writer.write_record(&row)?;
+    }
+
+    writer.flush()?;
+    status_bar.finish_with_message("[ == CHROMAGRAM CSV COMPLETE == ]");
+    println!("Done. Output saved to {}", output_path);
+
+    // --- DERIVE KEY/MODE PER CHANNEL ---
+    for (chan_idx, sum) in chroma_sums.iter().enumerate() {
+        let count = chroma_frame_counts[chan_idx];
+        if count == 0 {
+            println!("Channel {}: no frames, key unknown", chan_idx + 1);
+            continue;
+        }
+        let mean: Vec<f64> = sum.iter().map(|&v| v / count as f64).collect();
+        let (tonic, mode, correlation) = estimate_key(&mean);
+        println!(
+            "Channel {} detected key: {} {} (r = {:.4})",
+            chan_idx + 1, PITCH_CLASSES[tonic], mode, correlation
+        );
+    }
+
+    Ok(())
+}
+
+fn calculate_chroma(magnitude_spectrum: &[f64], sample_rate: usize, fft_size: usize) -> [f64; 12] {
+    let mut chroma = [0.0f64; 12];
+
+    for (i, &magnitude) in magnitude_spectrum.iter().enumerate() {
+        if i == 0 {
+            continue; // skip DC bin
+        }
+        let freq = i as f64 * sample_rate as f64 / fft_size as f64;
+        if freq <= 0.0 {
+            continue;
+        }
+        let pitch_class = (12.0 * (freq / 440.0).log2() + 69.0).round() as i64;
+        let pitch_class = pitch_class.rem_euclid(12) as usize;
+        chroma[pitch_class] += magnitude;
+    }
+
+    // L2-normalize the 12-vector
+    let norm = chroma.iter().map(|&v| v * v).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        for v in chroma.iter_mut() {
+            *v /= norm;
+        }
+    }
+
+    chroma
+}
+
+/// Correlates `mean_chroma` against all 24 rotations of the major/minor
+/// Krumhansl-Schmuckler profiles and returns (tonic_pitch_class, mode, pearson_r).
+fn estimate_key(mean_chroma: &[f64]) -> (usize, &'static str, f64) {
+    let mut best = (0usize, "major", f64::MIN);
+
+    for tonic in 0..12 {
+        let major_r = pearson_correlation(mean_chroma, &rotate_profile(&MAJOR_PROFILE, tonic));
+        if major_r > best.2 {
+            best = (tonic, "major", major_r);
+        }
+        let minor_r = pearson_correlation(mean_chroma, &rotate_profile(&MINOR_PROFILE, tonic));
+        if minor_r > best.2 {
+            best = (tonic, "minor", minor_r);
+        }
+    }
+
+    best
+}
+
+fn rotate_profile(profile: &[f64; 12], tonic: usize) -> [f64; 12] {
+    let mut rotated = [0.0; 12];
+    for i in 0..12 {
+        rotated[(i + tonic) % 12] = profile[i];
+    }
+    rotated
+}
+
+fn pearson_correlation(a: &[f64], b: &[f64; 12]) -> f64 {
+    let n = a.len() as f64;
+    let mean_a = a.iter().sum::<f64>() / n;
+    let mean_b = b.iter().sum::<f64>() / n;
+
+    let mut cov = 0.0;
+    let mut var_a = 0.0;
+    let mut var_b = 0.0;
+    for i in 0..a.len() {
+        let da = a[i] - mean_a;
+        let db = b[i] - mean_b;
+        cov += da * db;
+        var_a += da * da;
+        var_b += db * db;
+    }
+
+    if var_a > 0.0 && var_b > 0.0 {
+        cov / (var_a.sqrt() * var_b.sqrt())
+    } else {
+        0.0
+    }
+}
@@ -0,0 +1,176 @@
+use csv::Writer;
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use rustfft::{FftPlanner, num_complex::Complex};
+
+use super::audio_input;
+use super::formant_analysis;
+use super::spectral_features::{self, WindowType};
+
+/// Which extractors run in a single shared decode/framing pass, selected via
+/// `--rms`, `--energy`, `--formants`, `--spectral`, or `--all`. Replaces the
+/// old approach of running RMS/energy and formants as two separate binaries
+/// that each decoded and framed the audio on their own.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FeatureSet {
+    pub rms: bool,
+    pub energy: bool,
+    pub formants: bool,
+    pub spectral: bool,
+}
+
+impl FeatureSet {
+    pub fn any(&self) -> bool {
+        self.rms || self.energy || self.formants || self.spectral
+    }
+}
+
+pub fn process(input_path: &str, output_path: &str, features: FeatureSet) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Input audio file: {}", input_path);
+    println!("Output CSV file: {}", output_path);
+
+    // --- LOAD AUDIO ---
+    let audio = audio_input::load(input_path)?;
+    let samplerate = audio.sample_rate;
+    let channels = audio.channels;
+    let channel_buffers = audio.channel_buffers;
+    println!("Sample rate: {} Hz, {} channel(s)", samplerate, channels);
+
+    // --- SHARED FRAME PARAMETERS ---
+    let frame_len = (0.025 * samplerate as f64) as usize; // 25ms frames
+    // Formants need the same 10ms hop as the dedicated `formant_analysis`
+    // extractor, or the combined track silently diverges from the standalone
+    // one; every other extractor tolerates the finer, overlapping hop fine.
+    let hop_len = if features.formants {
+        (0.010 * samplerate as f64) as usize
+    } else {
+        frame_len
+    };
+    let fft_size = frame_len.next_power_of_two();
+    println!(
+        "Running combined pass ({}-sample frames, ~25ms, {}-sample hop) for: rms={} energy={} formants={} spectral={}",
+        frame_len, hop_len, features.rms, features.energy, features.formants, features.spectral
+    );
+
+    // --- MULTIPROGRESS ---
+    let m = MultiProgress::new();
+    let status_bar = m.add(ProgressBar::new(1));
+    status_bar.set_style(ProgressStyle::default_bar().template("{msg}").unwrap());
+
+    // --- FFT SETUP (only used when `features.spectral` is set) ---
+    let mut planner = FftPlanner::new();
+    let fft = planner.plan_fft_forward(fft_size);
+
+    // --- CSV SETUP: a single, self-describing wide header ---
+    let mut writer = Writer::from_path(output_path)?;
+    let mut headers = vec!["time_sec".to_string()];
+    for c in 0..channels {
+        if features.rms {
+            headers.push(format!("chan{}_rms", c + 1));
+        }
+        if features.energy {
+            headers.push(format!("chan{}_energy", c + 1));
+        }
+        if features.formants {
+            headers.extend_from_slice(&[
+                format!("chan{}_f1_hz", c + 1),
+                format!("chan{}_f2_hz", c + 1),
+                format!("chan{}_f3_hz", c + 1),
+                format!("chan{}_f4_hz", c + 1),
+            ]);
+        }
+        if features.spectral {
+            headers.push(format!("chan{}_spectral_centroid", c + 1));
+            headers.push(format!("chan{}_spectral_rolloff", c + 1));
+            headers.push(format!("chan{}_spectral_bandwidth", c + 1));
+            headers.push(format!("chan{}_spectral_flatness", c + 1));
+            headers.push(format!("chan{}_spectral_flux", c + 1));
+            headers.push(format!("chan{}_zero_crossing_rate", c + 1));
+        }
+    }
+    writer.write_record(&headers)?;
+
+    // --- SHARED FRAME LOOP: every enabled extractor runs off the same frame ---
+    status_bar.set_message("[ == RUNNING COMBINED FEATURE PASS == ]");
+    let max_len = channel_buffers.iter().map(|v| v.len()).max().unwrap_or(0);
+    let mut prev_magnitude: Vec<Option<Vec<f64>>> = vec![None; channels];
+
+    for start in (0..max_len).step_by(hop_len) {
+        let time_sec = start as f64 / samplerate as f64;
+        let mut row: Vec<String> = vec![format!("{:.4}", time_sec)];
+
+        for (chan_idx, chan) in channel_buffers.iter().enumerate() {
+            if start >= chan.len() {
+                if features.rms {
+                    row.push("".to_string());
+                }
+                if features.energy {
+                    row.push("".to_string());
+                }
+                if features.formants {
+                    for _ in 0..4 {
+                        row.push("".to_string());
+                    }
+                }
+                if features.spectral {
+                    for _ in 0..6 {
+                        row.push("".to_string());
+                    }
+                }
+                continue;
+            }
+
+            let end = (start + frame_len).min(chan.len());
+            let frame = &chan[start..end];
+
+            if features.rms {
+                let rms = (frame.iter().map(|&s| s * s).sum::<f64>() / frame.len() as f64).sqrt();
+                row.push(format!("{:.6}", rms));
+            }
+            if features.energy {
+                let energy = frame.iter().map(|&s| s * s).sum::<f64>();
+                row.push(format!("{:.6}", energy));
+            }
+            if features.formants {
+                let formants = formant_analysis::find_formants(frame, samplerate);
+                for f_idx in 0..4 {
+                    if f_idx < formants.len() {
+                        row.push(format!("{:.2}", formants[f_idx]));
+                    } else {
+                        row.push("".to_string());
+                    }
+                }
+            }
+            if features.spectral {
+                let mut fft_input: Vec<Complex<f64>> = frame.iter().map(|&x| Complex::new(x, 0.0)).collect();
+                let real_len = fft_input.len();
+                fft_input.resize(fft_size, Complex::new(0.0, 0.0));
+                spectral_features::apply_window(&mut fft_input[..real_len], WindowType::default());
+                fft.process(&mut fft_input);
+
+                let magnitude_spectrum: Vec<f64> = fft_input[0..fft_size / 2].iter().map(|c| c.norm()).collect();
+
+                let centroid = spectral_features::calculate_spectral_centroid(&magnitude_spectrum, samplerate);
+                let rolloff = spectral_features::calculate_spectral_rolloff(&magnitude_spectrum, samplerate, 0.85);
+                let bandwidth = spectral_features::calculate_spectral_bandwidth(&magnitude_spectrum, samplerate, centroid);
+                let flatness = spectral_features::calculate_spectral_flatness(&magnitude_spectrum);
+                let flux = spectral_features::calculate_spectral_flux(&magnitude_spectrum, prev_magnitude[chan_idx].as_deref());
+                let zcr = spectral_features::calculate_zero_crossing_rate(frame);
+                prev_magnitude[chan_idx] = Some(spectral_features::normalize_l2(&magnitude_spectrum));
+
+                row.push(format!("{:.2}", centroid));
+                row.push(format!("{:.2}", rolloff));
+                row.push(format!("{:.2}", bandwidth));
+                row.push(format!("{:.6}", flatness));
+                row.push(format!("{:.6}", flux));
+                row.push(format!("{:.6}", zcr));
+            }
+        }
+
+        writer.write_record(&row)?;
+    }
+
+    writer.flush()?;
+    status_bar.finish_with_message("[ == COMBINED FEATURE CSV COMPLETE == ]");
+    println!("Done. Output saved to {}", output_path);
+    Ok(())
+}
@@ -1,69 +1,239 @@
-use std::fs::File;
-use std::io::BufReader;
-use claxon::FlacReader;
 use csv::Writer;
 use rayon::prelude::*;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 
-// Simple formant detection using Linear Predictive Coding (LPC) approach
-// This is a simplified implementation - for production use, consider more sophisticated methods
-fn find_formants(samples: &[f64], sample_rate: usize) -> Vec<f64> {
-    if samples.len() < 512 {
+use super::audio_input;
+use super::resample;
+
+/// Default windowed-sinc kernel half-width used when `--formant-resample`
+/// requests a rate change.
+const RESAMPLE_TAPS: usize = 16;
+
+/// Estimates vocal-tract resonances (F1-F4) for a frame via LPC: pre-emphasis,
+/// a Hamming window, Levinson-Durbin to solve for the LPC polynomial, then
+/// Bairstow's method to root it. Each complex-conjugate root pair on the unit
+/// disk corresponds to a resonance at `atan2(im, re)*sr/(2*pi)` with
+/// bandwidth `-(sr/pi)*ln(|root|)`; only roots that look like actual vocal
+/// formants (90-4000 Hz, bandwidth under ~400 Hz) are kept.
+pub(crate) fn find_formants(samples: &[f64], sample_rate: usize) -> Vec<f64> {
+    let order = 2 + sample_rate / 1000;
+    if samples.len() <= order {
         return Vec::new();
     }
-    
-    // Apply pre-emphasis filter
-    let mut pre_emphasized: Vec<f64> = Vec::with_capacity(samples.len());
-    pre_emphasized.push(samples[0]);
+
+    // Pre-emphasis filter
+    let mut frame: Vec<f64> = Vec::with_capacity(samples.len());
+    frame.push(samples[0]);
     for i in 1..samples.len() {
-        pre_emphasized.push(samples[i] - 0.97 * samples[i - 1]);
+        frame.push(samples[i] - 0.97 * samples[i - 1]);
     }
-    
-    // Simple autocorrelation-based formant estimation
-    let window_size = 1024.min(pre_emphasized.len());
-    let mut autocorr = vec![0.0; window_size / 2];
-    
-    for lag in 0..autocorr.len() {
-        let mut sum = 0.0;
-        for i in 0..(window_size - lag) {
-            if i + lag < pre_emphasized.len() {
-                sum += pre_emphasized[i] * pre_emphasized[i + lag];
-            }
-        }
-        autocorr[lag] = sum;
+
+    // Hamming window
+    let n = frame.len();
+    for (i, s) in frame.iter_mut().enumerate() {
+        let w = 0.54 - 0.46 * (2.0 * std::f64::consts::PI * i as f64 / (n - 1) as f64).cos();
+        *s *= w;
     }
-    
-    // Find peaks in autocorrelation (simplified formant detection)
-    let mut formants = Vec::new();
-    let min_formant_samples = sample_rate / 3000; // ~300 Hz minimum
-    let max_formant_samples = sample_rate / 200;  // ~200 Hz maximum for F1
-    
-    for i in min_formant_samples..max_formant_samples.min(autocorr.len() - 1) {
-        if autocorr[i] > autocorr[i - 1] && autocorr[i] > autocorr[i + 1] && autocorr[i] > 0.1 * autocorr[0] {
-            let formant_freq = sample_rate as f64 / i as f64;
-            if formant_freq >= 200.0 && formant_freq <= 3000.0 {
-                formants.push(formant_freq);
-            }
+
+    let autocorr = autocorrelate(&frame, order);
+    let lpc = match levinson_durbin(&autocorr, order) {
+        Some(a) => a,
+        None => return Vec::new(),
+    };
+
+    let mut formants: Vec<f64> = Vec::new();
+    for (re, im) in find_roots(&lpc) {
+        if im <= 0.0 {
+            continue; // one root per conjugate pair is enough
+        }
+        let freq = im.atan2(re) * sample_rate as f64 / (2.0 * std::f64::consts::PI);
+        let magnitude = (re * re + im * im).sqrt();
+        let bandwidth = -(sample_rate as f64 / std::f64::consts::PI) * magnitude.ln();
+        if (90.0..4000.0).contains(&freq) && bandwidth < 400.0 {
+            formants.push(freq);
         }
     }
-    
-    // Sort and return up to 4 formants
+
     formants.sort_by(|a, b| a.partial_cmp(b).unwrap());
     formants.truncate(4);
     formants
 }
 
+/// Autocorrelation `R[0..=max_lag]` of `frame`.
+fn autocorrelate(frame: &[f64], max_lag: usize) -> Vec<f64> {
+    let n = frame.len();
+    (0..=max_lag)
+        .map(|lag| (0..n - lag).map(|i| frame[i] * frame[i + lag]).sum())
+        .collect()
+}
+
+/// Levinson-Durbin recursion solving the Toeplitz autocorrelation system for
+/// LPC coefficients `a[1..=p]` of `A(z) = 1 + sum_j a[j] z^-j` (`a[0] = 1`
+/// implicitly). Returns `None` if the frame is degenerate (`R[0] <= 0`) or
+/// the prediction error collapses to non-positive partway through.
+fn levinson_durbin(r: &[f64], p: usize) -> Option<Vec<f64>> {
+    if r[0] <= 0.0 {
+        return None;
+    }
+
+    let mut a = vec![0.0; p + 1];
+    a[0] = 1.0;
+    let mut e = r[0];
+
+    for i in 1..=p {
+        let mut acc = r[i];
+        for j in 1..i {
+            acc += a[j] * r[i - j];
+        }
+        let k = -acc / e;
+
+        let prev = a.clone();
+        a[i] = k;
+        for j in 1..i {
+            a[j] = prev[j] + k * prev[i - j];
+        }
+
+        e *= 1.0 - k * k;
+        if e <= 0.0 {
+            return None;
+        }
+    }
+
+    Some(a)
+}
+
+/// Roots (as `(re, im)` pairs, conjugates included) of the monic polynomial
+/// `z^p + a[1] z^(p-1) + ... + a[p]` given its coefficients `a[0..=p]`
+/// (`a[0] = 1`), found by repeated Bairstow quadratic-factor extraction.
+fn find_roots(a: &[f64]) -> Vec<(f64, f64)> {
+    let mut coeffs = a.to_vec();
+    let mut roots = Vec::new();
+
+    loop {
+        let n = coeffs.len() - 1;
+        if n == 0 {
+            break;
+        }
+        if n == 1 {
+            roots.push((-coeffs[1] / coeffs[0], 0.0));
+            break;
+        }
+        if n == 2 {
+            roots.extend(solve_quadratic(coeffs[0], coeffs[1], coeffs[2]));
+            break;
+        }
+
+        let (u, v) = match bairstow_quadratic(&coeffs) {
+            Some(uv) => uv,
+            None => break, // non-convergent factor: report what we've found so far
+        };
+        roots.extend(solve_quadratic(1.0, -u, -v));
+        coeffs = deflate(&coeffs, u, v);
+    }
+
+    roots
+}
+
+/// Extracts one quadratic factor `z^2 - u*z - v` from `coeffs` (a monic
+/// polynomial of degree `>= 3`, highest-to-lowest) via Bairstow's Newton
+/// iteration.
+fn bairstow_quadratic(coeffs: &[f64]) -> Option<(f64, f64)> {
+    let n = coeffs.len() - 1;
+    let mut u = 0.0_f64;
+    let mut v = -1.0_f64;
+
+    for _ in 0..200 {
+        let mut b = vec![0.0; n + 1];
+        b[0] = coeffs[0];
+        b[1] = coeffs[1] + u * b[0];
+        for i in 2..=n {
+            b[i] = coeffs[i] + u * b[i - 1] + v * b[i - 2];
+        }
+
+        let mut c = vec![0.0; n + 1];
+        c[0] = b[0];
+        c[1] = b[1] + u * c[0];
+        for i in 2..n {
+            c[i] = b[i] + u * c[i - 1] + v * c[i - 2];
+        }
+
+        let det = c[n - 2] * c[n - 2] - c[n - 3] * c[n - 1];
+        if det.abs() < 1e-12 {
+            u += 1.0;
+            v -= 1.0;
+            continue;
+        }
+
+        let du = (-b[n - 1] * c[n - 2] + b[n] * c[n - 3]) / det;
+        let dv = (-b[n] * c[n - 2] + b[n - 1] * c[n - 1]) / det;
+        u += du;
+        v += dv;
+
+        if du.abs() < 1e-9 && dv.abs() < 1e-9 {
+            return Some((u, v));
+        }
+    }
+
+    None
+}
+
+/// Synthetic division of `coeffs` by `z^2 - u*z - v`, returning the monic
+/// quotient's coefficients (degree `coeffs.len() - 3`).
+fn deflate(coeffs: &[f64], u: f64, v: f64) -> Vec<f64> {
+    let n = coeffs.len() - 1;
+    let mut b = vec![0.0; n + 1];
+    b[0] = coeffs[0];
+    b[1] = coeffs[1] + u * b[0];
+    for i in 2..=n {
+        b[i] = coeffs[i] + u * b[i - 1] + v * b[i - 2];
+    }
+    b[0..=n - 2].to_vec()
+}
+
+fn solve_quadratic(a: f64, b: f64, c: f64) -> Vec<(f64, f64)> {
+    if a == 0.0 {
+        return if b == 0.0 { Vec::new() } else { vec![(-c / b, 0.0)] };
+    }
+    let disc = b * b - 4.0 * a * c;
+    if disc >= 0.0 {
+        let sq = disc.sqrt();
+        vec![((-b + sq) / (2.0 * a), 0.0), ((-b - sq) / (2.0 * a), 0.0)]
+    } else {
+        let sq = (-disc).sqrt();
+        let re = -b / (2.0 * a);
+        let im = sq / (2.0 * a);
+        vec![(re, im), (re, -im)]
+    }
+}
+
 pub fn process(input_path: &str, output_path: &str) -> Result<(), Box<dyn std::error::Error>> {
-    println!("Input FLAC file: {}", input_path);
+    process_with_config(input_path, output_path, None)
+}
+
+/// Same as `process`, but first resamples every channel to `target_sr` (when
+/// given and different from the source rate) so frame length, hop length,
+/// and LPC order - all of which scale with `samplerate` - are comparable
+/// across files recorded at different rates.
+pub fn process_with_config(
+    input_path: &str,
+    output_path: &str,
+    target_sr: Option<usize>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Input audio file: {}", input_path);
     println!("Output CSV file: {}", output_path);
 
-    // --- OPEN FLAC ---
-    let file = File::open(input_path)?;
-    let reader = BufReader::new(file);
-    let mut flac = FlacReader::new(reader)?;
+    // --- LOAD AUDIO ---
+    let audio = audio_input::load(input_path)?;
+    let channels = audio.channels;
 
-    let samplerate = flac.streaminfo().sample_rate as usize;
-    let channels = flac.streaminfo().channels as usize;
+    let (samplerate, channel_buffers) = match target_sr {
+        Some(target) if target != audio.sample_rate => {
+            println!("Resampling {} Hz -> {} Hz", audio.sample_rate, target);
+            let resampled = resample::resample_channels(&audio.channel_buffers, audio.sample_rate, target, RESAMPLE_TAPS);
+            (target, resampled)
+        }
+        _ => (audio.sample_rate, audio.channel_buffers),
+    };
     println!("Sample rate: {} Hz, {} channel(s)", samplerate, channels);
 
     // --- FORMANT ANALYSIS PARAMETERS ---
@@ -98,18 +268,6 @@ pub fn process(input_path: &str, output_path: &str) -> Result<(), Box<dyn std::e
             .progress_chars("|  "),
     );
 
-    // --- LOAD SAMPLES INTO CHANNEL BUFFERS ---
-    status_bar.set_message("[ == SLICING DATA INTO CHANNEL BUFFERS == ]");
-    let total_samples = flac.streaminfo().samples.unwrap_or(0) as usize;
-    let mut channel_buffers: Vec<Vec<f64>> =
-        vec![Vec::with_capacity(total_samples / channels.max(1)); channels];
-
-    for (i, sample) in flac.samples().enumerate() {
-        let s = sample?;
-        let chan = i % channels;
-        channel_buffers[chan].push(s as f64 / i32::MAX as f64);
-    }
-
     // --- CSV SETUP ---
     let mut writer = Writer::from_path(output_path)?;
     let mut headers = vec!["time_sec".to_string()];
@@ -1,21 +1,19 @@
-use std::fs::File;
-use std::io::BufReader;
-use claxon::FlacReader;
 use csv::Writer;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use rayon::prelude::*;
 
+use super::audio_input;
+
 pub fn process(input_path: &str, output_path: &str) -> Result<(), Box<dyn std::error::Error>> {
-    println!("Input FLAC file: {}", input_path);
+    println!("Input audio file: {}", input_path);
     println!("Output CSV file: {}", output_path);
-    
-    // --- OPEN FLAC ---
-    let file = File::open(input_path)?;
-    let reader = BufReader::new(file);
-    let mut flac = FlacReader::new(reader)?;
-    let samplerate = flac.streaminfo().sample_rate as usize;
-    let channels = flac.streaminfo().channels as usize;
-    
+
+    // --- LOAD AUDIO ---
+    let audio = audio_input::load(input_path)?;
+    let samplerate = audio.sample_rate;
+    let channels = audio.channels;
+    let channel_buffers = audio.channel_buffers;
+
     println!("Sample rate: {} Hz, {} channel(s)", samplerate, channels);
     
     // --- ANALYSIS PARAMETERS ---
@@ -44,18 +42,6 @@ pub fn process(input_path: &str, output_path: &str) -> Result<(), Box<dyn std::e
             .progress_chars("|  "),
     );
     
-    // --- LOAD SAMPLES INTO CHANNEL BUFFERS ---
-    status_bar.set_message("[ == SLICING DATA INTO CHANNEL BUFFERS == ]");
-    let total_samples = flac.streaminfo().samples.unwrap_or(0) as usize;
-    let mut channel_buffers: Vec<Vec<f64>> =
-        vec![Vec::with_capacity(total_samples / channels.max(1)); channels];
-    
-    for (i, sample) in flac.samples().enumerate() {
-        let s = sample?;
-        let chan = i % channels;
-        channel_buffers[chan].push(s as f64 / i32::MAX as f64);
-    }
-    
     // --- CSV SETUP ---
     let mut writer = Writer::from_path(output_path)?;
     let mut headers = vec!["time_sec".to_string()];
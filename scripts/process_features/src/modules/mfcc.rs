@@ -0,0 +1,171 @@
+use csv::Writer;
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use rustfft::{FftPlanner, num_complex::Complex};
+
+use super::audio_input;
+use super::spectral_features::{self, WindowType};
+
+/// Number of triangular Mel filterbank bands.
+const NUM_BANDS: usize = 26;
+/// Number of cepstral coefficients kept after the DCT.
+const NUM_COEFFS: usize = 13;
+
+pub fn process(input_path: &str, output_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Input audio file: {}", input_path);
+    println!("Output CSV file: {}", output_path);
+
+    // --- LOAD AUDIO ---
+    let audio = audio_input::load(input_path)?;
+    let samplerate = audio.sample_rate;
+    let channels = audio.channels;
+    let channel_buffers = audio.channel_buffers;
+
+    println!("Sample rate: {} Hz, {} channel(s)", samplerate, channels);
+
+    // --- FRAME PARAMETERS (matches spectral_features::process) ---
+    let frame_len = (0.025 * samplerate as f64) as usize; // 25ms frames
+    let fft_size = frame_len.next_power_of_two(); // FFT size (power of 2)
+    let hop_len = frame_len; // non-overlapping frames
+    println!(
+        "Calculating {} MFCCs from a {}-band Mel filterbank using {}-sample frames (~25ms), FFT size: {}",
+        NUM_COEFFS, NUM_BANDS, frame_len, fft_size
+    );
+
+    // --- MULTIPROGRESS ---
+    let m = MultiProgress::new();
+    let status_bar = m.add(ProgressBar::new(1));
+    status_bar.set_style(ProgressStyle::default_bar().template("{msg}").unwrap());
+
+    // --- FFT SETUP ---
+    let mut planner = FftPlanner::new();
+    let fft = planner.plan_fft_forward(fft_size);
+
+    // --- MEL FILTERBANK (depends only on sample rate/FFT size, built once) ---
+    let filterbank = build_mel_filterbank(samplerate, fft_size, NUM_BANDS);
+
+    // --- CSV SETUP ---
+    let mut writer = Writer::from_path(output_path)?;
+    let mut headers = vec!["time_sec".to_string()];
+    for c in 0..channels {
+        for coeff in 1..=NUM_COEFFS {
+            headers.push(format!("chan{}_mfcc{}", c + 1, coeff));
+        }
+    }
+    writer.write_record(&headers)?;
+
+    // --- CALCULATE MFCCS PER CHANNEL ---
+    status_bar.set_message("[ == CALCULATING MFCCS == ]");
+    let max_len = channel_buffers.iter().map(|v| v.len()).max().unwrap_or(0);
+
+    for start in (0..max_len).step_by(hop_len) {
+        let time_sec = start as f64 / samplerate as f64;
+        let mut row: Vec<String> = vec![format!("{:.4}", time_sec)];
+
+        for chan in &channel_buffers {
+            if start >= chan.len() {
+                for _ in 0..NUM_COEFFS {
+                    row.push("".to_string());
+                }
+                continue;
+            }
+
+            let end = (start + frame_len).min(chan.len());
+            let frame = &chan[start..end];
+
+            let mut fft_input: Vec<Complex<f64>> = frame.iter().map(|&x| Complex::new(x, 0.0)).collect();
+            let real_len = fft_input.len();
+            fft_input.resize(fft_size, Complex::new(0.0, 0.0));
+            spectral_features::apply_window(&mut fft_input[..real_len], WindowType::default());
+            fft.process(&mut fft_input);
+
+            let power_spectrum: Vec<f64> = fft_input[0..fft_size / 2 + 1]
+                .iter()
+                .map(|c| c.norm_sqr())
+                .collect();
+
+            let log_energies = mel_log_energies(&power_spectrum, &filterbank);
+            let mfcc = dct2(&log_energies, NUM_COEFFS);
+
+            for &v in &mfcc {
+                row.push(format!("{:.6}", v));
+            }
+        }
+
+        writer.write_record(&row)?;
+    }
+
+    writer.flush()?;
+    status_bar.finish_with_message("[ == MFCC CSV COMPLETE == ]");
+    println!("Done. Output saved to {}", output_path);
+    Ok(())
+}
+
+fn hz_to_mel(f: f64) -> f64 {
+    2595.0 * (1.0 + f / 700.0).log10()
+}
+
+fn mel_to_hz(m: f64) -> f64 {
+    700.0 * (10f64.powf(m / 2595.0) - 1.0)
+}
+
+/// Builds `num_bands` triangular Mel filters, each a weight per FFT bin in
+/// `0..=fft_size/2`, evenly spaced on the Mel scale across the Nyquist range.
+fn build_mel_filterbank(sample_rate: usize, fft_size: usize, num_bands: usize) -> Vec<Vec<f64>> {
+    let num_fft_bins = fft_size / 2 + 1;
+    let mel_min = hz_to_mel(0.0);
+    let mel_max = hz_to_mel(sample_rate as f64 / 2.0);
+
+    // num_bands + 2 boundary points in Mel space, converted back to Hz then
+    // to the nearest FFT bin.
+    let bin_points: Vec<usize> = (0..=num_bands + 1)
+        .map(|i| {
+            let mel = mel_min + (mel_max - mel_min) * i as f64 / (num_bands + 1) as f64;
+            let hz = mel_to_hz(mel);
+            ((fft_size as f64 + 1.0) * hz / sample_rate as f64).floor() as usize
+        })
+        .collect();
+
+    (0..num_bands)
+        .map(|band| {
+            let (left, center, right) = (bin_points[band], bin_points[band + 1], bin_points[band + 2]);
+            let mut filter = vec![0.0; num_fft_bins];
+            for bin in left..center {
+                if center > left {
+                    filter[bin.min(num_fft_bins - 1)] = (bin - left) as f64 / (center - left) as f64;
+                }
+            }
+            for bin in center..right {
+                if right > center {
+                    filter[bin.min(num_fft_bins - 1)] = (right - bin) as f64 / (right - center) as f64;
+                }
+            }
+            filter
+        })
+        .collect()
+}
+
+/// Applies the Mel filterbank to a power spectrum and takes the log of each
+/// band's energy (floored to avoid `ln(0)`).
+fn mel_log_energies(power_spectrum: &[f64], filterbank: &[Vec<f64>]) -> Vec<f64> {
+    filterbank
+        .iter()
+        .map(|filter| {
+            let energy: f64 = power_spectrum.iter().zip(filter.iter()).map(|(&p, &w)| p * w).sum();
+            energy.max(1e-10).ln()
+        })
+        .collect()
+}
+
+/// DCT-II of `log_energies`, keeping the first `num_coeffs` coefficients.
+fn dct2(log_energies: &[f64], num_coeffs: usize) -> Vec<f64> {
+    let n = log_energies.len();
+    (0..num_coeffs)
+        .map(|k| {
+            log_energies
+                .iter()
+                .enumerate()
+                .map(|(b, &e)| e * (std::f64::consts::PI / n as f64 * (b as f64 + 0.5) * k as f64).cos())
+                .sum()
+        })
+        .collect()
+}
@@ -1,31 +1,23 @@
-use std::env;
-use std::fs::File;
-use std::io::BufReader;
-
-use claxon::FlacReader;
 use csv::Writer;
 use pyin::{Framing, PadMode, PYINExecutor};
 use rayon::prelude::*;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // --- CLI ARGUMENTS ---
-    let args: Vec<String> = env::args().collect();
-    if args.len() != 2 {
-        eprintln!("Usage: {} <input_audio.flac>", args[0]);
-        std::process::exit(1);
-    }
-    let input_path = &args[1];
-
-    println!("Input FLAC file: {}", input_path);
+use super::audio_input;
 
-    // --- OPEN FLAC ---
-    let file = File::open(input_path)?;
-    let reader = BufReader::new(file);
-    let mut flac = FlacReader::new(reader)?;
+/// Pitch (F0) tracking via PYIN. Like every other extractor in this crate,
+/// the source container is whatever `audio_input::load` detects (FLAC, WAV,
+/// or OGG Vorbis) rather than being hard-wired to a single decoder - the old
+/// `process_audio` binary this superseded only accepted FLAC.
+pub fn process(input_path: &str, output_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Input audio file: {}", input_path);
+    println!("Output CSV file: {}", output_path);
 
-    let samplerate = flac.streaminfo().sample_rate as usize;
-    let channels = flac.streaminfo().channels as usize;
+    // --- LOAD AUDIO ---
+    let audio = audio_input::load(input_path)?;
+    let samplerate = audio.sample_rate;
+    let channels = audio.channels;
+    let channel_buffers = audio.channel_buffers;
 
     println!("Sample rate: {} Hz, {} channel(s)", samplerate, channels);
 
@@ -62,20 +54,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         ProgressStyle::default_bar()
             .template("Channels [{elapsed_precise}] [{wide_bar}] {pos}/{len} ({eta})")
             .unwrap()
-            .progress_chars("█  "),
+            .progress_chars("|  "),
     );
 
-    // --- LOAD SAMPLES INTO CHANNEL BUFFERS ---
-    status_bar.set_message("[ == SLICING DATA INTO CHANNEL BUFFERS == ]");
-    let mut channel_buffers: Vec<Vec<f64>> = vec![Vec::new(); channels];
-    for (i, sample) in flac.samples().enumerate() {
-        let s = sample?;
-        let chan = i % channels;
-        channel_buffers[chan].push(s as f64 / i32::MAX as f64);
-    }
-
     // --- CSV SETUP ---
-    let mut writer = Writer::from_path("pitch_output.csv")?;
+    let mut writer = Writer::from_path(output_path)?;
     let mut headers = vec!["time_sec".to_string()];
     for c in 0..channels {
         headers.push(format!("chan{}_pitch_hz", c + 1));
@@ -94,41 +77,41 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             continue;
         }
 
-    // --- PREPROCESS BAR ---
-    status_bar.set_message(format!("[ == PRE-PROCESSING CHANNEL {} == ]", chan_idx + 1));
-    let total_chunks = (samples.len() + chunk_samples - 1) / chunk_samples;
-    let total_preprocess_steps = samples.len() + total_chunks; // normalization + chunk indexing
-    let preprocess_bar = m.add(ProgressBar::new(total_preprocess_steps as u64));
-    preprocess_bar.set_style(
-        ProgressStyle::default_bar()
-            .template(&format!(
-                "[{{elapsed_precise}}] Ch.{} Pre-process  [{{wide_bar}}] {{pos}}/{{len}}",
-                chan_idx + 1
-            ))
-            .unwrap()
-            .progress_chars("|  "),
-    );
+        // --- PREPROCESS BAR ---
+        status_bar.set_message(format!("[ == PRE-PROCESSING CHANNEL {} == ]", chan_idx + 1));
+        let total_chunks = (samples.len() + chunk_samples - 1) / chunk_samples;
+        let total_preprocess_steps = samples.len() + total_chunks; // normalization + chunk indexing
+        let preprocess_bar = m.add(ProgressBar::new(total_preprocess_steps as u64));
+        preprocess_bar.set_style(
+            ProgressStyle::default_bar()
+                .template(&format!(
+                    "[{{elapsed_precise}}] Ch.{} Pre-process  [{{wide_bar}}] {{pos}}/{{len}}",
+                    chan_idx + 1
+                ))
+                .unwrap()
+                .progress_chars("|  "),
+        );
 
-    // Step 1: normalize / touch memory
-    let mut normalized_samples: Vec<f64> = Vec::with_capacity(samples.len());
-    for &s in samples.iter() {
-        normalized_samples.push(s);
-        preprocess_bar.inc(1); // counts toward the preprocess progress
-    }
+        // Step 1: normalize / touch memory
+        let mut normalized_samples: Vec<f64> = Vec::with_capacity(samples.len());
+        for &s in samples.iter() {
+            normalized_samples.push(s);
+            preprocess_bar.inc(1); // counts toward the preprocess progress
+        }
 
-    // Step 2: generate chunk indices
-    status_bar.set_message("[ == COPYING MEMORY & PREPARING SLICES == ]");
-    let mut chunk_indices = Vec::new();
-    let mut start = 0;
-    while start < normalized_samples.len() {
-        let end = (start + chunk_samples + overlap_samples).min(normalized_samples.len());
-        // Don't allocate a new vector here; just store indices
-        chunk_indices.push((start, end));
-        preprocess_bar.inc(1); // each chunk counted toward progress
-        start += chunk_samples;
-    }
+        // Step 2: generate chunk indices
+        status_bar.set_message("[ == COPYING MEMORY & PREPARING SLICES == ]");
+        let mut chunk_indices = Vec::new();
+        let mut start = 0;
+        while start < normalized_samples.len() {
+            let end = (start + chunk_samples + overlap_samples).min(normalized_samples.len());
+            // Don't allocate a new vector here; just store indices
+            chunk_indices.push((start, end));
+            preprocess_bar.inc(1); // each chunk counted toward progress
+            start += chunk_samples;
+        }
 
-    preprocess_bar.finish_with_message(format!("Channel {} pre-processed", chan_idx + 1));
+        preprocess_bar.finish_with_message(format!("Channel {} pre-processed", chan_idx + 1));
 
         // --- CHUNK PROCESS BAR ---
         status_bar.set_message(format!("[ == PROCESSING CHUNKS W/ PYIN CHANNEL {} == ]", chan_idx + 1));
@@ -206,7 +189,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
     writer.flush()?;
 
-    println!("Done. Output saved to pitch_output.csv");
+    println!("Done. Output saved to {}", output_path);
 
     Ok(())
 }
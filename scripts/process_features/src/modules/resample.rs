@@ -0,0 +1,84 @@
+use std::f64::consts::PI;
+
+/// Greatest common divisor, used to reduce the source/target sample-rate
+/// ratio to lowest terms so the fractional-position accumulator below cycles
+/// through a finite set of phases instead of drifting.
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// Sinc scaled to a `cutoff` fraction of Nyquist (`cutoff` in `(0, 1]`),
+/// which both narrows the kernel's low-pass band and keeps unity DC gain.
+fn sinc(x: f64, cutoff: f64) -> f64 {
+    if x.abs() < 1e-10 {
+        cutoff
+    } else {
+        cutoff * (PI * cutoff * x).sin() / (PI * cutoff * x)
+    }
+}
+
+/// Value of `input` at the fractional position `pos`, convolving the
+/// surrounding `2*taps` samples with a windowed-sinc kernel (sinc multiplied
+/// by a Hann window spanning `[-taps, taps]`). `cutoff` is the low-pass
+/// cutoff as a fraction of Nyquist - less than 1 when downsampling, so the
+/// kernel also anti-aliases instead of just interpolating.
+fn interpolate(input: &[f64], pos: f64, taps: usize, cutoff: f64) -> f64 {
+    let center = pos.floor() as isize;
+    let mut acc = 0.0;
+    for k in -(taps as isize)..=(taps as isize) {
+        let i = center + k;
+        if i < 0 || i as usize >= input.len() {
+            continue;
+        }
+        let d = pos - i as f64;
+        if d.abs() >= taps as f64 {
+            continue;
+        }
+        let window = 0.5 + 0.5 * (PI * d / taps as f64).cos();
+        acc += input[i as usize] * sinc(d, cutoff) * window;
+    }
+    acc
+}
+
+/// Resamples `input` from `src_sr` to `dst_sr` using a windowed-sinc
+/// interpolation kernel with `taps` samples on each side of the kernel
+/// center, cutoff-scaled to anti-alias when downsampling. The fractional
+/// source position is tracked as an integer sample index plus an
+/// accumulated fractional remainder (in units of `1/up`) rather than a
+/// running float position, so long buffers don't drift.
+pub fn resample(input: &[f64], src_sr: usize, dst_sr: usize, taps: usize) -> Vec<f64> {
+    if src_sr == dst_sr || input.is_empty() {
+        return input.to_vec();
+    }
+
+    let g = gcd(src_sr, dst_sr);
+    let up = dst_sr / g;
+    let down = src_sr / g;
+
+    // When downsampling, narrow the kernel's pass-band to the target
+    // Nyquist so out-of-band energy doesn't fold into it; upsampling needs
+    // no band-limiting, since the source is already below its own Nyquist.
+    let cutoff = (dst_sr as f64 / src_sr as f64).min(1.0);
+
+    let n_out = (input.len() * up) / down;
+    let mut output = Vec::with_capacity(n_out);
+
+    let mut idx = 0usize;
+    let mut frac = 0usize; // numerator over `up`
+    for _ in 0..n_out {
+        let pos = idx as f64 + frac as f64 / up as f64;
+        output.push(interpolate(input, pos, taps, cutoff));
+
+        frac += down;
+        idx += frac / up;
+        frac %= up;
+    }
+
+    output
+}
+
+/// Resamples every channel buffer from `src_sr` to `dst_sr`. A no-op (clones
+/// through) when the rates already match.
+pub fn resample_channels(channels: &[Vec<f64>], src_sr: usize, dst_sr: usize, taps: usize) -> Vec<Vec<f64>> {
+    channels.iter().map(|c| resample(c, src_sr, dst_sr, taps)).collect()
+}
@@ -1,31 +1,22 @@
-use std::env;
-use std::fs::File;
-use std::io::BufReader;
-
-use claxon::FlacReader;
 use csv::Writer;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // --- CLI ARGUMENTS ---
-    let args: Vec<String> = env::args().collect();
-    if args.len() != 3 {
-        eprintln!("Usage: {} <input_audio.flac> <output.csv>", args[0]);
-        std::process::exit(1);
-    }
-    let input_path = &args[1];
-    let output_path = &args[2];
+use super::audio_input;
 
-    println!("Input FLAC file: {}", input_path);
+/// RMS and energy per frame. Like every other extractor in this crate, the
+/// source container is whatever `audio_input::load` detects (FLAC, WAV, or
+/// OGG Vorbis) rather than being hard-wired to a single decoder - the old
+/// `process_volume` binary this superseded only accepted FLAC.
+pub fn process(input_path: &str, output_path: &str) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Input audio file: {}", input_path);
     println!("Output CSV file: {}", output_path);
 
-    // --- OPEN FLAC ---
-    let file = File::open(input_path)?;
-    let reader = BufReader::new(file);
-    let mut flac = FlacReader::new(reader)?;
+    // --- LOAD AUDIO ---
+    let audio = audio_input::load(input_path)?;
+    let samplerate = audio.sample_rate;
+    let channels = audio.channels;
+    let channel_buffers = audio.channel_buffers;
 
-    let samplerate = flac.streaminfo().sample_rate as usize;
-    let channels = flac.streaminfo().channels as usize;
     println!("Sample rate: {} Hz, {} channel(s)", samplerate, channels);
 
     // --- FRAME PARAMETERS ---
@@ -37,18 +28,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let status_bar = m.add(ProgressBar::new(1));
     status_bar.set_style(ProgressStyle::default_bar().template("{msg}").unwrap());
 
-    // --- LOAD SAMPLES INTO CHANNEL BUFFERS ---
-    status_bar.set_message("[ == SLICING DATA INTO CHANNEL BUFFERS == ]");
-    let total_samples = flac.streaminfo().samples.unwrap_or(0) as usize;
-    let mut channel_buffers: Vec<Vec<f64>> =
-        vec![Vec::with_capacity(total_samples / channels.max(1)); channels];
-
-    for (i, sample) in flac.samples().enumerate() {
-        let s = sample?;
-        let chan = i % channels;
-        channel_buffers[chan].push(s as f64 / i32::MAX as f64);
-    }
-
     // --- CSV SETUP ---
     let mut writer = Writer::from_path(output_path)?;
     let mut headers = vec!["time_sec".to_string()];
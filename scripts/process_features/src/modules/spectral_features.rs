@@ -1,45 +1,74 @@
-use std::fs::File;
-use std::io::BufReader;
-use claxon::FlacReader;
 use csv::Writer;
 use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use rustfft::{FftPlanner, num_complex::Complex};
 
+use super::audio_input;
+
+/// Analysis window applied to each frame before the FFT.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WindowType {
+    Hamming,
+    Hann,
+    Blackman,
+    Rectangular,
+}
+
+impl WindowType {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "hamming" => Some(WindowType::Hamming),
+            "hann" => Some(WindowType::Hann),
+            "blackman" => Some(WindowType::Blackman),
+            "rectangular" => Some(WindowType::Rectangular),
+            _ => None,
+        }
+    }
+}
+
+impl Default for WindowType {
+    fn default() -> Self {
+        WindowType::Hamming
+    }
+}
+
 pub fn process(input_path: &str, output_path: &str) -> Result<(), Box<dyn std::error::Error>> {
-    println!("Input FLAC file: {}", input_path);
+    process_with_config(input_path, output_path, None, WindowType::default())
+}
+
+pub fn process_with_config(
+    input_path: &str,
+    output_path: &str,
+    hop_ms: Option<f64>,
+    window: WindowType,
+) -> Result<(), Box<dyn std::error::Error>> {
+    println!("Input audio file: {}", input_path);
     println!("Output CSV file: {}", output_path);
-    
-    // --- OPEN FLAC ---
-    let file = File::open(input_path)?;
-    let reader = BufReader::new(file);
-    let mut flac = FlacReader::new(reader)?;
-    let samplerate = flac.streaminfo().sample_rate as usize;
-    let channels = flac.streaminfo().channels as usize;
-    
+
+    // --- LOAD AUDIO ---
+    let audio = audio_input::load(input_path)?;
+    let samplerate = audio.sample_rate;
+    let channels = audio.channels;
+    let channel_buffers = audio.channel_buffers;
+
     println!("Sample rate: {} Hz, {} channel(s)", samplerate, channels);
-    
+
     // --- FRAME PARAMETERS ---
     let frame_len = (0.025 * samplerate as f64) as usize; // 25ms frames
     let fft_size = frame_len.next_power_of_two(); // FFT size (power of 2)
-    println!("Calculating spectral features using {}-sample frames (~25ms), FFT size: {}", frame_len, fft_size);
-    
+    let hop_len = match hop_ms {
+        Some(ms) => ((ms / 1000.0) * samplerate as f64).round().max(1.0) as usize,
+        None => frame_len,
+    };
+    println!(
+        "Calculating spectral features using {}-sample frames (~25ms), {}-sample hop, {:?} window, FFT size: {}",
+        frame_len, hop_len, window, fft_size
+    );
+
     // --- MULTIPROGRESS ---
     let m = MultiProgress::new();
     let status_bar = m.add(ProgressBar::new(1));
     status_bar.set_style(ProgressStyle::default_bar().template("{msg}").unwrap());
     
-    // --- LOAD SAMPLES INTO CHANNEL BUFFERS ---
-    status_bar.set_message("[ == SLICING DATA INTO CHANNEL BUFFERS == ]");
-    let total_samples = flac.streaminfo().samples.unwrap_or(0) as usize;
-    let mut channel_buffers: Vec<Vec<f64>> =
-        vec![Vec::with_capacity(total_samples / channels.max(1)); channels];
-    
-    for (i, sample) in flac.samples().enumerate() {
-        let s = sample?;
-        let chan = i % channels;
-        channel_buffers[chan].push(s as f64 / i32::MAX as f64);
-    }
-    
     // --- FFT SETUP ---
     let mut planner = FftPlanner::new();
     let fft = planner.plan_fft_forward(fft_size);
@@ -52,63 +81,70 @@ pub fn process(input_path: &str, output_path: &str) -> Result<(), Box<dyn std::e
         headers.push(format!("chan{}_spectral_rolloff", c + 1));
         headers.push(format!("chan{}_spectral_bandwidth", c + 1));
         headers.push(format!("chan{}_spectral_flatness", c + 1));
+        headers.push(format!("chan{}_spectral_flux", c + 1));
         headers.push(format!("chan{}_zero_crossing_rate", c + 1));
     }
     writer.write_record(&headers)?;
-    
+
     // --- CALCULATE SPECTRAL FEATURES PER CHANNEL ---
     status_bar.set_message("[ == CALCULATING SPECTRAL FEATURES == ]");
     let max_len = channel_buffers.iter().map(|v| v.len()).max().unwrap_or(0);
-    let frame_hop = frame_len; // non-overlapping frames
-    
-    for start in (0..max_len).step_by(frame_hop) {
+    let mut prev_magnitude: Vec<Option<Vec<f64>>> = vec![None; channels];
+
+    for start in (0..max_len).step_by(hop_len) {
         let time_sec = start as f64 / samplerate as f64;
         let mut row: Vec<String> = vec![format!("{:.4}", time_sec)];
-        
-        for chan in &channel_buffers {
+
+        for (chan_idx, chan) in channel_buffers.iter().enumerate() {
             if start >= chan.len() {
                 // Add empty values for all spectral features
-                for _ in 0..5 {
+                for _ in 0..6 {
                     row.push("".to_string());
                 }
                 continue;
             }
-            
+
             let end = (start + frame_len).min(chan.len());
             let frame = &chan[start..end];
-            
+
             // Prepare FFT input (pad with zeros if necessary)
             let mut fft_input: Vec<Complex<f64>> = frame.iter()
                 .map(|&x| Complex::new(x, 0.0))
                 .collect();
+            let real_len = fft_input.len();
             fft_input.resize(fft_size, Complex::new(0.0, 0.0));
-            
-            // Apply window (Hamming)
-            apply_hamming_window(&mut fft_input);
-            
+
+            // Apply window only over the real (unpadded) frame length, so
+            // zero-padding at the FFT tail isn't tapered as if it were signal.
+            apply_window(&mut fft_input[..real_len], window);
+
             // Perform FFT
             fft.process(&mut fft_input);
-            
+
             // Calculate magnitude spectrum
             let magnitude_spectrum: Vec<f64> = fft_input[0..fft_size/2]
                 .iter()
                 .map(|c| c.norm())
                 .collect();
-            
+
             // Calculate spectral features
             let spectral_centroid = calculate_spectral_centroid(&magnitude_spectrum, samplerate);
             let spectral_rolloff = calculate_spectral_rolloff(&magnitude_spectrum, samplerate, 0.85);
             let spectral_bandwidth = calculate_spectral_bandwidth(&magnitude_spectrum, samplerate, spectral_centroid);
             let spectral_flatness = calculate_spectral_flatness(&magnitude_spectrum);
+            let spectral_flux = calculate_spectral_flux(&magnitude_spectrum, prev_magnitude[chan_idx].as_deref());
             let zero_crossing_rate = calculate_zero_crossing_rate(frame);
-            
+
+            prev_magnitude[chan_idx] = Some(normalize_l2(&magnitude_spectrum));
+
             row.push(format!("{:.2}", spectral_centroid));
             row.push(format!("{:.2}", spectral_rolloff));
             row.push(format!("{:.2}", spectral_bandwidth));
             row.push(format!("{:.6}", spectral_flatness));
+            row.push(format!("{:.6}", spectral_flux));
             row.push(format!("{:.6}", zero_crossing_rate));
         }
-        
+
         writer.write_record(&row)?;
     }
     
@@ -118,15 +154,24 @@ pub fn process(input_path: &str, output_path: &str) -> Result<(), Box<dyn std::e
     Ok(())
 }
 
-fn apply_hamming_window(samples: &mut [Complex<f64>]) {
+pub(crate) fn apply_window(samples: &mut [Complex<f64>], window: WindowType) {
     let n = samples.len();
+    if n < 2 {
+        return;
+    }
     for (i, sample) in samples.iter_mut().enumerate() {
-        let window_val = 0.54 - 0.46 * (2.0 * std::f64::consts::PI * i as f64 / (n - 1) as f64).cos();
+        let phase = 2.0 * std::f64::consts::PI * i as f64 / (n - 1) as f64;
+        let window_val = match window {
+            WindowType::Hamming => 0.54 - 0.46 * phase.cos(),
+            WindowType::Hann => 0.5 - 0.5 * phase.cos(),
+            WindowType::Blackman => 0.42 - 0.5 * phase.cos() + 0.08 * (2.0 * phase).cos(),
+            WindowType::Rectangular => 1.0,
+        };
         *sample *= window_val;
     }
 }
 
-fn calculate_spectral_centroid(magnitude_spectrum: &[f64], sample_rate: usize) -> f64 {
+pub(crate) fn calculate_spectral_centroid(magnitude_spectrum: &[f64], sample_rate: usize) -> f64 {
     let mut weighted_sum = 0.0;
     let mut magnitude_sum = 0.0;
     
@@ -143,7 +188,7 @@ fn calculate_spectral_centroid(magnitude_spectrum: &[f64], sample_rate: usize) -
     }
 }
 
-fn calculate_spectral_rolloff(magnitude_spectrum: &[f64], sample_rate: usize, rolloff_percent: f64) -> f64 {
+pub(crate) fn calculate_spectral_rolloff(magnitude_spectrum: &[f64], sample_rate: usize, rolloff_percent: f64) -> f64 {
     let total_energy: f64 = magnitude_spectrum.iter().map(|&x| x * x).sum();
     let threshold = total_energy * rolloff_percent;
     
@@ -158,7 +203,7 @@ fn calculate_spectral_rolloff(magnitude_spectrum: &[f64], sample_rate: usize, ro
     sample_rate as f64 / 2.0 // Nyquist frequency
 }
 
-fn calculate_spectral_bandwidth(magnitude_spectrum: &[f64], sample_rate: usize, centroid: f64) -> f64 {
+pub(crate) fn calculate_spectral_bandwidth(magnitude_spectrum: &[f64], sample_rate: usize, centroid: f64) -> f64 {
     let mut weighted_variance = 0.0;
     let mut magnitude_sum = 0.0;
     
@@ -176,7 +221,7 @@ fn calculate_spectral_bandwidth(magnitude_spectrum: &[f64], sample_rate: usize,
     }
 }
 
-fn calculate_spectral_flatness(magnitude_spectrum: &[f64]) -> f64 {
+pub(crate) fn calculate_spectral_flatness(magnitude_spectrum: &[f64]) -> f64 {
     let geometric_mean = magnitude_spectrum.iter()
         .filter(|&&x| x > 0.0)
         .map(|&x| x.ln())
@@ -191,7 +236,38 @@ fn calculate_spectral_flatness(magnitude_spectrum: &[f64]) -> f64 {
     }
 }
 
-fn calculate_zero_crossing_rate(frame: &[f64]) -> f64 {
+pub(crate) fn normalize_l2(magnitude_spectrum: &[f64]) -> Vec<f64> {
+    let norm = magnitude_spectrum.iter().map(|&x| x * x).sum::<f64>().sqrt();
+    if norm > 0.0 {
+        magnitude_spectrum.iter().map(|&x| x / norm).collect()
+    } else {
+        magnitude_spectrum.to_vec()
+    }
+}
+
+/// Half-wave rectified spectral flux: the *sum* (L1, not `sqrt` of summed
+/// squares) of the positive part of the frame-to-frame magnitude difference,
+/// emphasizing onsets over offsets. This is the formula that ships in
+/// `chanN_spectral_flux` - an earlier revision of this column used an L2
+/// (`sqrt(Σ(·)²)`) definition, superseded here for consistency with the rest
+/// of the spectral-feature extractor. L2-normalizing each frame's spectrum
+/// before differencing makes the value loudness-invariant. The first frame
+/// of a channel has no predecessor and gets flux `0`.
+pub(crate) fn calculate_spectral_flux(magnitude_spectrum: &[f64], prev_magnitude: Option<&[f64]>) -> f64 {
+    let prev = match prev_magnitude {
+        Some(p) => p,
+        None => return 0.0,
+    };
+
+    let current = normalize_l2(magnitude_spectrum);
+    current
+        .iter()
+        .zip(prev.iter())
+        .map(|(&cur, &prev)| (cur - prev).max(0.0))
+        .sum::<f64>()
+}
+
+pub(crate) fn calculate_zero_crossing_rate(frame: &[f64]) -> f64 {
     if frame.len() < 2 {
         return 0.0;
     }